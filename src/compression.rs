@@ -0,0 +1,73 @@
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Client-side compression applied to an object before it's uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Zstd,
+    Xz,
+}
+
+impl Default for Compression {
+    fn default() -> Self {
+        Compression::None
+    }
+}
+
+impl Compression {
+    /// Extension appended to the object key when this compression is used.
+    pub fn extension(self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Zstd => Some("zst"),
+            Compression::Xz => Some("xz"),
+        }
+    }
+
+    /// Value to send as the object's `Content-Encoding` metadata, for codecs
+    /// where that's a meaningful HTTP content-coding.
+    ///
+    /// `zstd` is a registered content-coding (RFC 8878): a zstd-aware HTTP
+    /// client (browser, `curl --compressed`, ...) will transparently
+    /// decompress it on download, handing the caller decompressed bytes
+    /// under the still-`.zst`-suffixed object name. That's normal HTTP
+    /// semantics, not a bug, but it means "download via a plain URL" does
+    /// not get you the compressed artifact back byte-for-byte — fetch the
+    /// object without `Accept-Encoding: zstd` (e.g. the MinIO console, or
+    /// `curl` without `--compressed`) if you need the stored bytes as-is.
+    ///
+    /// `xz` has no registered content-coding, so nothing decodes it
+    /// automatically; advertising it as `Content-Encoding` would be
+    /// misleading rather than useful, so we don't set it.
+    pub fn content_encoding(self) -> Option<&'static str> {
+        match self {
+            Compression::None | Compression::Xz => None,
+            Compression::Zstd => Some("zstd"),
+        }
+    }
+
+    /// Compresses `data` in memory, trading `level` off for ratio: a higher
+    /// level means smaller output but more CPU and memory spent compressing.
+    /// `level` is clamped to whatever each codec actually accepts (zstd:
+    /// 1..=22, xz: 0..=9) so an out-of-range setting can't panic/fail the
+    /// upload; 0 means "use the codec's own default".
+    pub fn compress(self, data: Vec<u8>, level: u32) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data),
+            Compression::Zstd => {
+                let level = if level == 0 { 3 } else { level.clamp(1, 22) as i32 };
+                Ok(zstd::stream::encode_all(data.as_slice(), level)?)
+            }
+            Compression::Xz => {
+                let preset = if level == 0 { 6 } else { level.clamp(0, 9) };
+                let mut encoder = xz2::write::XzEncoder::new(Vec::new(), preset);
+                encoder.write_all(&data)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Persistent upload history: one JSON line per attempted upload, pruned to
+//! the most recent `history_limit` entries (mirrors a simple
+//! prune-to-N-most-recent policy) on every write.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub path: String,
+    pub url: Option<String>,
+    pub success: bool,
+}
+
+impl HistoryEntry {
+    pub fn new(path: &str, url: Option<String>, success: bool) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            timestamp,
+            path: path.to_string(),
+            url,
+            success,
+        }
+    }
+}
+
+fn history_path() -> Result<PathBuf> {
+    let mut dir = dirs::data_dir().ok_or_else(|| anyhow::anyhow!("no APPDATA directory"))?;
+    dir.push("MinioUploader");
+    fs::create_dir_all(&dir)?;
+    dir.push("history.jsonl");
+    Ok(dir)
+}
+
+/// Returns every recorded entry, oldest first.
+pub fn read_all() -> Result<Vec<HistoryEntry>> {
+    let path = history_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs::File::open(&path)?;
+    let reader = BufReader::new(file);
+    let mut entries = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Ok(entry) = serde_json::from_str::<HistoryEntry>(&line) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Appends `entry` to the history log, then drops the oldest entries beyond
+/// the most recent `keep`.
+pub fn record(entry: HistoryEntry, keep: usize) -> Result<()> {
+    let path = history_path()?;
+
+    let mut entries = read_all().unwrap_or_default();
+    entries.push(entry);
+    if entries.len() > keep {
+        let excess = entries.len() - keep;
+        entries.drain(0..excess);
+    }
+
+    let mut file = fs::File::create(&path)?;
+    for entry in &entries {
+        writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Windows install/uninstall subsystem: copies the exe into `%APPDATA%`,
+//! registers the context menu entry, creates a Start Menu shortcut, and
+//! lists the app in "Apps & features" via the CurrentVersion\Uninstall key.
+
+use std::env;
+use std::fs;
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::Result;
+use mslnk::ShellLink;
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+
+const DETACHED_PROCESS: u32 = 0x0000_0008;
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+const APP_NAME: &str = "MinIO Uploader";
+const INSTALL_DIR_NAME: &str = "MinioUploader";
+const UNINSTALL_KEY: &str = "Software\\Microsoft\\Windows\\CurrentVersion\\Uninstall\\MinioUploader";
+const DISPLAY_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const SETTINGS_TEMPLATE: &str = r#"# MinIO Uploader settings
+endpoint = "https://play.min.io"
+access_key = "YOUR_ACCESS_KEY"
+secret_key = "YOUR_SECRET_KEY"
+bucket = "your-bucket"
+"#;
+
+fn install_dir() -> Result<PathBuf> {
+    let mut dir = dirs::data_dir().ok_or_else(|| anyhow::anyhow!("no APPDATA directory"))?;
+    dir.push(INSTALL_DIR_NAME);
+    Ok(dir)
+}
+
+fn installed_exe_path() -> Result<PathBuf> {
+    let exe_name = env::current_exe()?
+        .file_name()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("minio_uploader.exe"));
+    Ok(install_dir()?.join(exe_name))
+}
+
+fn start_menu_shortcut_path() -> Result<PathBuf> {
+    let mut dir = dirs::data_dir().ok_or_else(|| anyhow::anyhow!("no APPDATA directory"))?;
+    dir.push("Microsoft\\Windows\\Start Menu\\Programs");
+    Ok(dir.join(format!("{}.lnk", APP_NAME)))
+}
+
+/// Copies the running exe into `%APPDATA%\MinioUploader`, drops a default
+/// `Settings.toml` template if one isn't already there, creates a Start Menu
+/// shortcut, and registers the app so it shows up in "Apps & features".
+pub fn install() -> Result<()> {
+    let dir = install_dir()?;
+    fs::create_dir_all(&dir)?;
+
+    let current_exe = env::current_exe()?;
+    let installed_exe = installed_exe_path()?;
+    fs::copy(&current_exe, &installed_exe)?;
+
+    let settings_path = dir.join("Settings.toml");
+    if !settings_path.exists() {
+        fs::write(&settings_path, SETTINGS_TEMPLATE)?;
+    }
+
+    let shortcut_path = start_menu_shortcut_path()?;
+    if let Some(parent) = shortcut_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let shortcut = ShellLink::new(&installed_exe)?;
+    shortcut.create_lnk(&shortcut_path)?;
+
+    register_uninstall_entry(&installed_exe)?;
+    // Point the context menu at the copy in %APPDATA%, not whatever exe the
+    // user happened to run --install from (e.g. a Downloads/temp folder).
+    register_context_menu_for(&installed_exe)?;
+
+    Ok(())
+}
+
+/// Reverses everything `install()` did, including the context menu keys
+/// that have always been self-registered on every run.
+pub fn uninstall() -> Result<()> {
+    remove_context_menu_registration()?;
+
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let _ = hkcu.delete_subkey_all(UNINSTALL_KEY);
+
+    if let Ok(shortcut_path) = start_menu_shortcut_path() {
+        let _ = fs::remove_file(shortcut_path);
+    }
+
+    // The running exe lives inside `install_dir()`, so it can't delete that
+    // directory out from under itself: on Windows a file can't be removed
+    // while it's still open by a running process. Hand the deletion off to
+    // a short-lived detached helper that waits for this process to exit.
+    if let Ok(dir) = install_dir() {
+        schedule_dir_deletion(&dir)?;
+    }
+
+    Ok(())
+}
+
+/// Spawns a detached `cmd` helper that waits a couple of seconds (long
+/// enough for this process to exit) and then removes `dir`, including the
+/// exe copy inside it.
+fn schedule_dir_deletion(dir: &Path) -> Result<()> {
+    let command = format!(
+        "timeout /T 2 /NOBREAK >nul & rmdir /S /Q \"{}\"",
+        dir.display()
+    );
+    Command::new("cmd")
+        .args(["/C", &command])
+        .creation_flags(DETACHED_PROCESS | CREATE_NO_WINDOW)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+fn register_uninstall_entry(installed_exe: &PathBuf) -> Result<()> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(UNINSTALL_KEY)?;
+
+    let exe_str = installed_exe.display().to_string();
+    let estimated_size_kb = fs::metadata(installed_exe).map(|m| m.len() / 1024).unwrap_or(0) as u32;
+
+    key.set_value("DisplayName", &APP_NAME)?;
+    key.set_value("DisplayIcon", &exe_str)?;
+    key.set_value("UninstallString", &format!("\"{}\" --uninstall", exe_str))?;
+    key.set_value("DisplayVersion", &DISPLAY_VERSION)?;
+    key.set_value("EstimatedSize", &estimated_size_kb)?;
+    key.set_value("NoModify", &1u32)?;
+    key.set_value("NoRepair", &1u32)?;
+
+    Ok(())
+}
+
+/// Registers the context menu against whichever exe happens to be running —
+/// the existing self-registering-on-every-run behavior for the portable case.
+pub fn ensure_context_menu_registered() -> Result<()> {
+    register_context_menu_for(&env::current_exe()?)
+}
+
+fn register_context_menu_for(exe: &Path) -> Result<()> {
+    // Create HKCU\Software\Classes\*\shell\MinIO Uploader\command
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let base_path = "Software\\Classes\\*\\shell\\MinIO Uploader";
+    let command_path = format!("{}\\command", base_path);
+
+    let exe_str = exe.display().to_string();
+
+    // If already registered for this exact exe, there's nothing to do.
+    if let Ok(cmd_key) = hkcu.open_subkey(&command_path) {
+        if let Ok(existing) = cmd_key.get_value::<String, _>("") {
+            if existing.contains(&exe_str) {
+                return Ok(());
+            }
+        }
+    }
+
+    // Create main key
+    let (key, _) = hkcu.create_subkey(base_path)?;
+    key.set_value("", &"Upload to MinIO")?;
+    key.set_value("Icon", &exe_str)?;
+
+    // Create command key with quoted path and %1
+    let (cmd_key, _) = hkcu.create_subkey(command_path)?;
+    let command = format!("\"{}\" \"%1\"", exe_str);
+    cmd_key.set_value("", &command)?;
+
+    Ok(())
+}
+
+pub fn remove_context_menu_registration() -> Result<()> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let base_path = "Software\\Classes\\*\\shell\\MinIO Uploader";
+    if hkcu.open_subkey(base_path).is_err() {
+        return Ok(());
+    }
+    hkcu.delete_subkey_all(base_path)?;
+    Ok(())
+}
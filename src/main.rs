@@ -1,83 +1,47 @@
 #![cfg_attr(target_os = "windows", windows_subsystem = "windows")]
 use anyhow::Result;
-use config::{Config, File};
 use native_dialog::{MessageDialog, MessageType};
-use serde::Deserialize;
 use std::env;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::fs::File as TokioFile;
 use tokio::io::AsyncReadExt;
 
 use minio::s3::builders::ObjectContent;
-use minio::s3::client::ClientBuilder;
+use minio::s3::client::{Client, ClientBuilder};
 use minio::s3::creds::StaticProvider;
 use minio::s3::http::BaseUrl;
+use minio::s3::multimap::Multimap;
 use arboard::Clipboard;
 use urlencoding::encode;
+
+mod pipeline;
+use pipeline::{Pipeline, Step, StepOutcome};
+
+mod settings;
+use settings::Settings;
+
+mod compression;
+use compression::Compression;
+
+mod history;
+use history::HistoryEntry;
+
 #[cfg(windows)]
-use winreg::{enums::HKEY_CURRENT_USER, RegKey};
-
-#[derive(Debug, Deserialize)]
-struct Settings {
-    endpoint: String,
-    access_key: String,
-    secret_key: String,
-    bucket: String,
-}
+mod install;
 
-impl Settings {
-    pub fn new() -> Result<Self> {
-        // Priority 1: %APPDATA%/MinioUploader/Settings.toml
-        let appdata_config = dirs::data_dir()
-            .map(|mut path| {
-                path.push("MinioUploader");
-                path.push("Settings.toml");
-                path
-            })
-            .filter(|p| p.exists());
-
-        // Priority 2: Executable directory/Settings.toml
-        let exe_dir_config = env::current_exe()
-            .ok()
-            .map(|mut path| {
-                path.pop();
-                path.push("Settings.toml");
-                path
-            })
-            .filter(|p| p.exists());
-
-        // Try appdata first, then exe directory
-        let config_path = appdata_config
-            .or(exe_dir_config)
-            .ok_or_else(|| {
-                let appdata_path = dirs::data_dir()
-                    .map(|mut p| {
-                        p.push("MinioUploader");
-                        p.push("Settings.toml");
-                        p.display().to_string()
-                    })
-                    .unwrap_or_else(|| "%APPDATA%\\MinioUploader\\Settings.toml".to_string());
-                
-                let exe_path = env::current_exe()
-                    .ok()
-                    .map(|mut p| {
-                        p.pop();
-                        p.push("Settings.toml");
-                        p.display().to_string()
-                    })
-                    .unwrap_or_else(|| "<executable_dir>\\Settings.toml".to_string());
-
-                let error_msg = format!(
-                    "Configuration file not found. Please create 'Settings.toml' in one of the following locations:\n\n1. {} (recommended)\n2. {}",
-                    appdata_path, exe_path
-                );
-                show_error_dialog(&error_msg);
-                anyhow::anyhow!("Config file not found")
-            })?;
-
-        let builder = Config::builder().add_source(File::from(config_path.as_path()));
-        let settings = builder.build()?.try_deserialize()?;
-        Ok(settings)
+/// Formats a byte count as a human-readable size for progress/summary text.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
     }
 }
 
@@ -99,19 +63,92 @@ fn show_info_dialog(message: &str) {
         .unwrap();
 }
 
+/// Shows the most recent upload history entries and, as the easiest way to
+/// "re-copy" a past link in a drag-and-drop app with no list UI, copies the
+/// most recent successful URL back to the clipboard.
+fn show_history_dialog() -> Result<()> {
+    let mut entries = history::read_all()?;
+    entries.reverse(); // most recent first
+
+    if entries.is_empty() {
+        show_info_dialog("暂无上传历史记录。");
+        return Ok(());
+    }
+
+    if let Some(latest_url) = entries.iter().find_map(|e| e.url.clone()) {
+        if let Err(e) = Clipboard::new().and_then(|mut c| c.set_text(latest_url)) {
+            show_error_dialog(&format!("复制最近链接到剪切板失败: {}", e));
+        }
+    }
+
+    let mut message = String::from("最近的上传记录（已将最新链接复制到剪切板）:\n");
+    for entry in entries.iter().take(20) {
+        let status = if entry.success { "成功" } else { "失败" };
+        message.push_str(&format!(
+            "\n[{}] {} -> {}",
+            status,
+            entry.path,
+            entry.url.as_deref().unwrap_or("-")
+        ));
+    }
+
+    show_info_dialog(&message);
+    Ok(())
+}
+
 async fn run() -> Result<()> {
-    // Parse args first, in case we need to uninstall without requiring Settings.toml
+    // Parse args first, in case we need to uninstall/bundle without requiring Settings.toml
     let args: Vec<String> = env::args().collect();
 
+    if let Some(idx) = args.iter().position(|a| a.eq_ignore_ascii_case("--embed-config")) {
+        let config_path = match args.get(idx + 1) {
+            Some(p) => PathBuf::from(p),
+            None => {
+                show_error_dialog("--embed-config requires a path, e.g. --embed-config Settings.toml");
+                return Err(anyhow::anyhow!("missing --embed-config path"));
+            }
+        };
+        let exe_path = env::current_exe()?;
+        match settings::embed_config(&exe_path, &config_path) {
+            Ok(portable_path) => {
+                show_info_dialog(&format!("已生成便携版:\n{}", portable_path.display()));
+                return Ok(());
+            }
+            Err(e) => {
+                show_error_dialog(&format!("生成便携版失败: {:?}", e));
+                return Err(e);
+            }
+        }
+    }
+
+    if args.iter().any(|a| a.eq_ignore_ascii_case("--history") || a.eq_ignore_ascii_case("/history")) {
+        show_history_dialog()?;
+        return Ok(());
+    }
+
+    #[cfg(windows)]
+    if args.iter().any(|a| a.eq_ignore_ascii_case("--install") || a.eq_ignore_ascii_case("/install")) {
+        match install::install() {
+            Ok(_) => {
+                show_info_dialog("安装完成，已在“应用和功能”中注册。");
+                return Ok(());
+            }
+            Err(e) => {
+                show_error_dialog(&format!("安装失败: {:?}", e));
+                return Err(e);
+            }
+        }
+    }
+
     #[cfg(windows)]
     if args.iter().any(|a| a.eq_ignore_ascii_case("--uninstall") || a.eq_ignore_ascii_case("/uninstall")) {
-        match remove_context_menu_registration() {
+        match install::uninstall() {
             Ok(_) => {
-                show_info_dialog("已移除右键菜单 (Current User)。");
+                show_info_dialog("已卸载 MinIO Uploader (Current User)。");
                 return Ok(());
             }
             Err(e) => {
-                show_error_dialog(&format!("移除右键菜单失败: {:?}", e));
+                show_error_dialog(&format!("卸载失败: {:?}", e));
                 return Err(e);
             }
         }
@@ -119,7 +156,7 @@ async fn run() -> Result<()> {
 
     #[cfg(windows)]
     {
-        if let Err(e) = ensure_context_menu_registered() {
+        if let Err(e) = install::ensure_context_menu_registered() {
             // Non-fatal; show dialog to inform the user
             show_error_dialog(&format!("Failed to register context menu: {:?}", e));
         }
@@ -134,19 +171,14 @@ async fn run() -> Result<()> {
         return Err(anyhow::anyhow!("No file path provided"));
     }
 
-    let file_path_str = &args[1];
-    let file_path = Path::new(file_path_str);
-
-    if !file_path.exists() {
-        show_error_dialog(&format!("File does not exist: {}", file_path_str));
-        return Err(anyhow::anyhow!("File not found"));
+    let paths: Vec<PathBuf> = args[1..].iter().map(PathBuf::from).collect();
+    for p in &paths {
+        if !p.exists() {
+            show_error_dialog(&format!("File does not exist: {}", p.display()));
+            return Err(anyhow::anyhow!("File not found"));
+        }
     }
 
-    let file_name = file_path
-        .file_name()
-        .and_then(|s| s.to_str())
-        .unwrap_or("unknown_file");
-
     let base_url: BaseUrl = settings.endpoint.parse()?;
     let client = ClientBuilder::new(base_url)
         .provider(Some(Box::new(StaticProvider::new(
@@ -156,83 +188,178 @@ async fn run() -> Result<()> {
         ))))
         .build()?;
 
-    let mut file = TokioFile::open(&file_path).await?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer).await?;
+    // Multi-select drag-and-drop and folder uploads both land here as a
+    // sequence of steps: a directory is expanded into its files lazily by
+    // the pipeline, so we don't need to walk it up front.
+    let steps = paths
+        .into_iter()
+        .map(|path| {
+            if path.is_dir() {
+                Step::UploadDir {
+                    root: path.clone(),
+                    path,
+                    recursive: true,
+                }
+            } else {
+                Step::UploadFile { path, key: None }
+            }
+        })
+        .collect();
 
-    let content = ObjectContent::from(buffer);
-    let result = client
-        .put_object_content(&settings.bucket, file_name, content)
-        .send()
-        .await;
+    let outcomes = Pipeline::new(steps).run(&client, &settings).await;
+    record_history(&outcomes, settings.history_limit);
+    report_outcomes(outcomes)
+}
 
-    // Build object URL
-    let mut endpoint = settings.endpoint.trim().to_string();
-    if endpoint.ends_with('/') {
-        endpoint.pop();
-    }
-    let object_url = format!(
-        "{}/{}/{}",
-        endpoint,
-        &settings.bucket,
-        encode(file_name)
-    );
-    match result {
-        Ok(_) => {
-            let mut copied = true;
-            if let Err(e) = Clipboard::new().and_then(|mut c| c.set_text(object_url.clone())) {
-                copied = false;
-                show_error_dialog(&format!("上传成功，但复制到剪切板失败: {}\nURL: {}", e, object_url));
-            }
-            if copied {
-                show_info_dialog(&format!("上传成功，链接已复制到剪切板:\n{}", object_url));
-            }
-            Ok(())
-        }
-        Err(e) => {
-            show_error_dialog(&format!("上传失败: {}", e));
-            Err(anyhow::anyhow!(e))
+/// Appends one history entry per upload attempt; failures here are
+/// non-fatal since the upload itself already succeeded or failed on its own.
+fn record_history(outcomes: &[StepOutcome], keep: usize) {
+    for outcome in outcomes {
+        let entry = HistoryEntry::new(
+            &outcome.path.display().to_string(),
+            outcome.url.clone(),
+            outcome.success(),
+        );
+        if let Err(e) = history::record(entry, keep) {
+            eprintln!("Failed to record upload history: {:?}", e);
         }
     }
 }
 
-#[cfg(windows)]
-fn ensure_context_menu_registered() -> Result<()> {
-    // Create HKCU\Software\Classes\*\shell\MinIO Uploader\command
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let base_path = "Software\\Classes\\*\\shell\\MinIO Uploader";
-    let command_path = format!("{}\\command", base_path);
-
-    // If command key exists, assume already registered
-    if hkcu.open_subkey(&command_path).is_ok() {
-        return Ok(());
+/// Copies every successful object URL (newline-joined) to the clipboard and
+/// shows a summary dialog of what succeeded and what failed.
+fn report_outcomes(outcomes: Vec<StepOutcome>) -> Result<()> {
+    let (succeeded, failed): (Vec<_>, Vec<_>) =
+        outcomes.into_iter().partition(|o| o.success());
+
+    let urls: Vec<String> = succeeded.iter().filter_map(|o| o.url.clone()).collect();
+    if !urls.is_empty() {
+        if let Err(e) = Clipboard::new().and_then(|mut c| c.set_text(urls.join("\n"))) {
+            show_error_dialog(&format!("上传完成，但复制到剪切板失败: {}", e));
+        }
     }
 
-    let exe = env::current_exe()?;
-    let exe_str = exe.display().to_string();
+    let total_bytes: u64 = succeeded.iter().filter_map(|o| o.bytes).sum();
+    let mut summary = format!(
+        "成功 {} 个（共 {}），失败 {} 个。",
+        succeeded.len(),
+        format_size(total_bytes),
+        failed.len()
+    );
+    // Per-file progress: which files went through and how many bytes each
+    // one sent.
+    for outcome in &succeeded {
+        let size = format_size(outcome.bytes.unwrap_or(0));
+        summary.push_str(&format!("\n{}: {} 已上传", outcome.path.display(), size));
+    }
+    if !urls.is_empty() {
+        summary.push_str(&format!("\n\n链接已复制到剪切板:\n{}", urls.join("\n")));
+    }
+    for outcome in &failed {
+        summary.push_str(&format!(
+            "\n\n{}: {}",
+            outcome.path.display(),
+            outcome.error.as_deref().unwrap_or("unknown error")
+        ));
+    }
 
-    // Create main key
-    let (key, _) = hkcu.create_subkey(base_path)?;
-    key.set_value("", &"Upload to MinIO")?;
-    key.set_value("Icon", &exe_str)?;
+    if failed.is_empty() {
+        show_info_dialog(&summary);
+        Ok(())
+    } else {
+        show_error_dialog(&summary);
+        Err(anyhow::anyhow!("{} upload(s) failed", failed.len()))
+    }
+}
 
-    // Create command key with quoted path and %1
-    let (cmd_key, _) = hkcu.create_subkey(command_path)?;
-    let command = format!("\"{}\" \"%1\"", exe_str);
-    cmd_key.set_value("", &command)?;
+/// Uploads a single file and returns its object URL and the number of bytes
+/// actually sent.
+///
+/// `key` is the object key relative to the directory root it was found
+/// under (with `/` separators), so nested files like `2023/a.jpg` and
+/// `2024/a.jpg` keep distinct keys instead of colliding on their bare file
+/// name; pass `None` for a file given directly on the command line, which
+/// just uses its own name.
+///
+/// Files below `settings.multipart_threshold` are read fully into memory as
+/// before. Larger files are streamed straight off disk in bounded chunks and
+/// sent as a multipart PUT sized by `settings.part_size`, so memory stays
+/// flat regardless of file size. When `settings.compression` is enabled, the
+/// file is compressed in memory first (its final size isn't known ahead of
+/// time, so the streaming path is skipped) and the object key gets the
+/// codec's extension; only zstd also sets `Content-Encoding` metadata.
+async fn upload_file(
+    client: &Client,
+    settings: &Settings,
+    file_path: &Path,
+    key: Option<&str>,
+) -> Result<(String, u64)> {
+    let base_key = match key {
+        Some(k) => k.to_string(),
+        None => file_path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown_file")
+            .to_string(),
+    };
+    let object_name = match settings.compression.extension() {
+        Some(ext) => format!("{}.{}", base_key, ext),
+        None => base_key,
+    };
 
-    Ok(())
-}
+    let file = TokioFile::open(file_path).await?;
+    let file_size = file.metadata().await?.len();
 
-#[cfg(windows)]
-fn remove_context_menu_registration() -> Result<()> {
-    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
-    let base_path = "Software\\Classes\\*\\shell\\MinIO Uploader";
-    if hkcu.open_subkey(base_path).is_err() {
-        return Ok(());
+    let uploaded_bytes;
+    let put = if settings.compression != Compression::None {
+        let mut file = file;
+        let mut buffer = Vec::with_capacity(file_size as usize);
+        file.read_to_end(&mut buffer).await?;
+        let compressed = settings
+            .compression
+            .compress(buffer, settings.compression_level)?;
+        uploaded_bytes = compressed.len() as u64;
+
+        let content = ObjectContent::from(compressed);
+        let mut put = client.put_object_content(&settings.bucket, &object_name, content);
+        if let Some(content_encoding) = settings.compression.content_encoding() {
+            let mut headers = Multimap::new();
+            headers.insert("Content-Encoding".to_string(), content_encoding.to_string());
+            put = put.extra_headers(Some(headers));
+        }
+        put
+    } else if file_size >= settings.multipart_threshold {
+        uploaded_bytes = file_size;
+        let content = ObjectContent::new_from_stream(file, Some(file_size));
+        client
+            .put_object_content(&settings.bucket, &object_name, content)
+            .part_size(settings.part_size)
+    } else {
+        uploaded_bytes = file_size;
+        let mut file = file;
+        let mut buffer = Vec::with_capacity(file_size as usize);
+        file.read_to_end(&mut buffer).await?;
+        let content = ObjectContent::from(buffer);
+        client.put_object_content(&settings.bucket, &object_name, content)
+    };
+
+    put.send().await?;
+
+    let mut endpoint = settings.endpoint.trim().to_string();
+    if endpoint.ends_with('/') {
+        endpoint.pop();
     }
-    hkcu.delete_subkey_all(base_path)?;
-    Ok(())
+    // Encode each path segment on its own so a nested key's `/` separators
+    // survive instead of becoming `%2F`.
+    let encoded_name = object_name
+        .split('/')
+        .map(encode)
+        .collect::<Vec<_>>()
+        .join("/");
+    Ok((
+        format!("{}/{}/{}", endpoint, &settings.bucket, encoded_name),
+        uploaded_bytes,
+    ))
 }
 
 #[tokio::main]
@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+
+use minio::s3::client::Client;
+
+use crate::Settings;
+
+/// A unit of work for the upload pipeline. `UploadDir` is expanded into
+/// child `UploadFile` (and further `UploadDir`) steps at run time, once the
+/// directory can actually be listed.
+#[derive(Debug, Clone)]
+pub enum Step {
+    UploadFile {
+        path: PathBuf,
+        /// Object key relative to the `UploadDir` root this file came from,
+        /// using `/` as the separator regardless of platform. `None` for a
+        /// file passed directly as an argument, which just uses its name.
+        key: Option<String>,
+    },
+    UploadDir {
+        path: PathBuf,
+        /// The directory originally requested, kept through recursion so
+        /// nested files get a key relative to it instead of colliding on
+        /// their bare file name.
+        root: PathBuf,
+        recursive: bool,
+    },
+}
+
+/// Result of running a single `UploadFile` step, or of an `UploadDir` step
+/// whose directory was empty or could not be read.
+#[derive(Debug)]
+pub struct StepOutcome {
+    pub path: PathBuf,
+    pub url: Option<String>,
+    pub bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl StepOutcome {
+    pub fn success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Runs a list of steps sequentially, expanding directories as it goes, and
+/// collects one outcome per file it tried to upload (succeeded or failed),
+/// plus one per directory that was empty or could not be read.
+pub struct Pipeline {
+    steps: Vec<Step>,
+}
+
+impl Pipeline {
+    pub fn new(steps: Vec<Step>) -> Self {
+        Self { steps }
+    }
+
+    pub async fn run(self, client: &Client, settings: &Settings) -> Vec<StepOutcome> {
+        let mut outcomes = Vec::new();
+        let mut queue: Vec<Step> = self.steps.into_iter().rev().collect();
+
+        while let Some(step) = queue.pop() {
+            match step {
+                Step::UploadFile { path, key } => {
+                    outcomes.push(upload_one(client, settings, &path, key.as_deref()).await);
+                }
+                Step::UploadDir {
+                    path,
+                    root,
+                    recursive,
+                } => match expand_dir(&path, &root, recursive) {
+                    Ok(children) if children.is_empty() => {
+                        outcomes.push(StepOutcome {
+                            path,
+                            url: None,
+                            bytes: None,
+                            error: Some("directory contains no files to upload".to_string()),
+                        });
+                    }
+                    Ok(children) => {
+                        for child in children.into_iter().rev() {
+                            queue.push(child);
+                        }
+                    }
+                    Err(e) => {
+                        outcomes.push(StepOutcome {
+                            path,
+                            url: None,
+                            bytes: None,
+                            error: Some(format!("failed to read directory: {}", e)),
+                        });
+                    }
+                },
+            }
+        }
+
+        outcomes
+    }
+}
+
+fn expand_dir(dir: &Path, root: &Path, recursive: bool) -> std::io::Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    let entries = std::fs::read_dir(dir)?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if recursive {
+                steps.push(Step::UploadDir {
+                    path,
+                    root: root.to_path_buf(),
+                    recursive,
+                });
+            }
+        } else {
+            let key = path
+                .strip_prefix(root)
+                .ok()
+                .map(|rel| rel.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect::<Vec<_>>().join("/"));
+            steps.push(Step::UploadFile { path, key });
+        }
+    }
+
+    Ok(steps)
+}
+
+async fn upload_one(
+    client: &Client,
+    settings: &Settings,
+    path: &Path,
+    key: Option<&str>,
+) -> StepOutcome {
+    match crate::upload_file(client, settings, path, key).await {
+        Ok((url, bytes)) => StepOutcome {
+            path: path.to_path_buf(),
+            url: Some(url),
+            bytes: Some(bytes),
+            error: None,
+        },
+        Err(e) => StepOutcome {
+            path: path.to_path_buf(),
+            url: None,
+            bytes: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
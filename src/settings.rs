@@ -0,0 +1,186 @@
+use std::env;
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use config::{Config, File};
+use serde::Deserialize;
+
+use crate::compression::Compression;
+use crate::show_error_dialog;
+
+/// Marks an appended config trailer in a portable build: it follows the
+/// config bytes and comes right before their 8-byte little-endian length. See [`Settings::from_embedded_trailer`]
+/// and [`embed_config`].
+const EMBED_MAGIC: &[u8] = b"MINIOUPLOADER_EMBEDDED_CFG_V1";
+
+#[derive(Debug, Deserialize)]
+pub struct Settings {
+    pub endpoint: String,
+    pub access_key: String,
+    pub secret_key: String,
+    pub bucket: String,
+    /// Files at or above this size (in bytes) are sent as a multipart PUT
+    /// instead of a single request.
+    #[serde(default = "default_multipart_threshold")]
+    pub multipart_threshold: u64,
+    /// Size (in bytes) of each part of a multipart upload.
+    #[serde(default = "default_part_size")]
+    pub part_size: u64,
+    /// Transparent client-side compression applied before upload.
+    #[serde(default)]
+    pub compression: Compression,
+    /// Compression level passed to the chosen codec (zstd: 1..=22, xz:
+    /// 0..=9, clamped either way); 0 means "use the codec's own default".
+    #[serde(default)]
+    pub compression_level: u32,
+    /// How many upload history entries to keep in `history.jsonl`.
+    #[serde(default = "default_history_limit")]
+    pub history_limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    100
+}
+
+fn default_multipart_threshold() -> u64 {
+    100 * 1024 * 1024 // 100 MiB
+}
+
+fn default_part_size() -> u64 {
+    16 * 1024 * 1024 // 16 MiB
+}
+
+impl Settings {
+    pub fn new() -> Result<Self> {
+        // Priority 0: config baked into this exe's own trailer by `--embed-config`.
+        if let Some(settings) = Self::from_embedded_trailer()? {
+            return Ok(settings);
+        }
+
+        // Priority 1: %APPDATA%/MinioUploader/Settings.toml
+        let appdata_config = dirs::data_dir()
+            .map(|mut path| {
+                path.push("MinioUploader");
+                path.push("Settings.toml");
+                path
+            })
+            .filter(|p| p.exists());
+
+        // Priority 2: Executable directory/Settings.toml
+        let exe_dir_config = env::current_exe()
+            .ok()
+            .map(|mut path| {
+                path.pop();
+                path.push("Settings.toml");
+                path
+            })
+            .filter(|p| p.exists());
+
+        // Try appdata first, then exe directory
+        let config_path = appdata_config
+            .or(exe_dir_config)
+            .ok_or_else(|| {
+                let appdata_path = dirs::data_dir()
+                    .map(|mut p| {
+                        p.push("MinioUploader");
+                        p.push("Settings.toml");
+                        p.display().to_string()
+                    })
+                    .unwrap_or_else(|| "%APPDATA%\\MinioUploader\\Settings.toml".to_string());
+
+                let exe_path = env::current_exe()
+                    .ok()
+                    .map(|mut p| {
+                        p.pop();
+                        p.push("Settings.toml");
+                        p.display().to_string()
+                    })
+                    .unwrap_or_else(|| "<executable_dir>\\Settings.toml".to_string());
+
+                let error_msg = format!(
+                    "Configuration file not found. Please create 'Settings.toml' in one of the following locations:\n\n1. {} (recommended)\n2. {}",
+                    appdata_path, exe_path
+                );
+                show_error_dialog(&error_msg);
+                anyhow::anyhow!("Config file not found")
+            })?;
+
+        let builder = Config::builder().add_source(File::from(config_path.as_path()));
+        let settings = builder.build()?.try_deserialize()?;
+        Ok(settings)
+    }
+
+    /// Looks for a `[config bytes][EMBED_MAGIC][len: u64 LE]` trailer appended
+    /// to the end of the currently running exe (written by [`embed_config`])
+    /// and, if present, parses it as TOML settings.
+    fn from_embedded_trailer() -> Result<Option<Self>> {
+        let exe_path = env::current_exe()?;
+        let mut file = std::fs::File::open(&exe_path)?;
+        let file_len = file.metadata()?.len();
+
+        let footer_len = EMBED_MAGIC.len() as u64 + 8;
+        if file_len < footer_len {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::End(-(footer_len as i64)))?;
+        let mut footer = vec![0u8; footer_len as usize];
+        file.read_exact(&mut footer)?;
+
+        let (magic, len_bytes) = footer.split_at(EMBED_MAGIC.len());
+        if magic != EMBED_MAGIC {
+            return Ok(None);
+        }
+        let config_len = u64::from_le_bytes(len_bytes.try_into().unwrap());
+        let total_trailer_len = match config_len.checked_add(footer_len) {
+            Some(len) => len,
+            None => return Ok(None),
+        };
+        if config_len == 0 || total_trailer_len > file_len {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::End(-(total_trailer_len as i64)))?;
+        let mut config_bytes = vec![0u8; config_len as usize];
+        file.read_exact(&mut config_bytes)?;
+        let toml_str = String::from_utf8(config_bytes)?;
+
+        let builder = Config::builder().add_source(File::from_str(&toml_str, config::FileFormat::Toml));
+        let settings = builder.build()?.try_deserialize()?;
+        Ok(Some(settings))
+    }
+}
+
+/// Appends `config_path`'s contents plus the embed trailer to a copy of
+/// `exe_path`, producing a portable build that needs no loose Settings.toml.
+/// Returns the path of the new, self-contained exe.
+pub fn embed_config(exe_path: &Path, config_path: &Path) -> Result<PathBuf> {
+    let config_bytes = fs::read(config_path)?;
+
+    // Fail fast if the file we're about to bake in doesn't even parse.
+    let builder = Config::builder().add_source(File::from(config_path));
+    let _settings: Settings = builder.build()?.try_deserialize()?;
+
+    let mut exe_bytes = fs::read(exe_path)?;
+    exe_bytes.extend_from_slice(&config_bytes);
+    exe_bytes.extend_from_slice(EMBED_MAGIC);
+    exe_bytes.extend_from_slice(&(config_bytes.len() as u64).to_le_bytes());
+
+    let portable_path = portable_copy_path(exe_path);
+    fs::write(&portable_path, exe_bytes)?;
+    Ok(portable_path)
+}
+
+fn portable_copy_path(exe_path: &Path) -> PathBuf {
+    let stem = exe_path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("minio_uploader");
+    let file_name = match exe_path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => format!("{}_portable.{}", stem, ext),
+        None => format!("{}_portable", stem),
+    };
+    exe_path.with_file_name(file_name)
+}